@@ -0,0 +1,58 @@
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    Json,
+};
+use sqlx::PgPool;
+
+use crate::erp::order_module::model::order::{
+    bind_query_arg, GetOrderStatsQuery, GetOrdersQuery, Order, OrderStatsBucket,
+};
+
+type ApiResult<T> = Result<T, (StatusCode, String)>;
+
+fn internal_error(err: sqlx::Error) -> (StatusCode, String) {
+    (StatusCode::INTERNAL_SERVER_ERROR, err.to_string())
+}
+
+/// Lists orders matching the given filter.
+#[utoipa::path(
+    get,
+    path = "/orders",
+    params(GetOrdersQuery),
+    responses((status = 200, body = [Order])),
+    tag = "orders"
+)]
+pub async fn list_orders(
+    State(pool): State<PgPool>,
+    Query(filter): Query<GetOrdersQuery>,
+) -> ApiResult<Json<Vec<Order>>> {
+    let (sql, args) = filter.to_query_builder("SELECT orders.* FROM orders").build();
+    let mut query = sqlx::query_as::<_, Order>(&sql);
+    for arg in &args {
+        query = bind_query_arg(query, arg);
+    }
+    let rows = query.fetch_all(&pool).await.map_err(internal_error)?;
+    Ok(Json(rows))
+}
+
+/// Aggregates orders matching the filter into buckets of the requested dimension.
+#[utoipa::path(
+    get,
+    path = "/orders/stats",
+    params(GetOrderStatsQuery),
+    responses((status = 200, body = [OrderStatsBucket])),
+    tag = "orders"
+)]
+pub async fn get_order_stats(
+    State(pool): State<PgPool>,
+    Query(query): Query<GetOrderStatsQuery>,
+) -> ApiResult<Json<Vec<OrderStatsBucket>>> {
+    let (sql, args) = query.to_query_builder().build();
+    let mut q = sqlx::query_as::<_, OrderStatsBucket>(&sql);
+    for arg in &args {
+        q = bind_query_arg(q, arg);
+    }
+    let rows = q.fetch_all(&pool).await.map_err(internal_error)?;
+    Ok(Json(rows))
+}
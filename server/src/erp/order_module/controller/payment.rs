@@ -0,0 +1,67 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use sqlx::PgPool;
+
+use crate::erp::order_module::model::order::Payment;
+
+type ApiResult<T> = Result<T, (StatusCode, String)>;
+
+fn internal_error(err: sqlx::Error) -> (StatusCode, String) {
+    (StatusCode::INTERNAL_SERVER_ERROR, err.to_string())
+}
+
+/// Appends a payment to an order's settlement ledger.
+#[utoipa::path(
+    post,
+    path = "/orders/{order_id}/payments",
+    params(("order_id" = i64, Path, description = "Order id")),
+    request_body = Payment,
+    responses((status = 200, body = Payment)),
+    tag = "payments"
+)]
+pub async fn append_payment(
+    State(pool): State<PgPool>,
+    Path(order_id): Path<i64>,
+    Json(payment): Json<Payment>,
+) -> ApiResult<Json<Payment>> {
+    let row = sqlx::query_as::<_, Payment>(
+        "INSERT INTO payments \
+         (order_id, amount, currency, date, method, note, created_by_user_id, voided) \
+         VALUES ($1, $2, $3, $4, $5, $6, $7, false) \
+         RETURNING *",
+    )
+    .bind(order_id)
+    .bind(payment.amount)
+    .bind(payment.currency)
+    .bind(payment.date)
+    .bind(payment.method)
+    .bind(payment.note)
+    .bind(payment.created_by_user_id)
+    .fetch_one(&pool)
+    .await
+    .map_err(internal_error)?;
+    Ok(Json(row))
+}
+
+/// Voids a payment, excluding it from settlement sums while keeping it for audit history.
+#[utoipa::path(
+    post,
+    path = "/payments/{id}/void",
+    params(("id" = i64, Path, description = "Payment id")),
+    responses((status = 200, body = Payment)),
+    tag = "payments"
+)]
+pub async fn void_payment(
+    State(pool): State<PgPool>,
+    Path(id): Path<i64>,
+) -> ApiResult<Json<Payment>> {
+    let row = sqlx::query_as::<_, Payment>("UPDATE payments SET voided = true WHERE id = $1 RETURNING *")
+        .bind(id)
+        .fetch_one(&pool)
+        .await
+        .map_err(internal_error)?;
+    Ok(Json(row))
+}
@@ -0,0 +1,164 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use chrono::Utc;
+use sqlx::PgPool;
+
+use crate::erp::order_module::model::order::{
+    bind_query_arg, GetScheduledOrdersQuery, Order, ScheduledOrder,
+};
+
+type ApiResult<T> = Result<T, (StatusCode, String)>;
+
+fn internal_error(err: sqlx::Error) -> (StatusCode, String) {
+    (StatusCode::INTERNAL_SERVER_ERROR, err.to_string())
+}
+
+/// Creates a new recurring order template.
+#[utoipa::path(
+    post,
+    path = "/scheduled-orders",
+    request_body = ScheduledOrder,
+    responses((status = 200, body = ScheduledOrder)),
+    tag = "scheduled-orders"
+)]
+pub async fn create_scheduled_order(
+    State(pool): State<PgPool>,
+    Json(template): Json<ScheduledOrder>,
+) -> ApiResult<Json<ScheduledOrder>> {
+    let row = sqlx::query_as::<_, ScheduledOrder>(
+        "INSERT INTO scheduled_orders \
+         (created_by_user_id, warehouse_id, person_related_id, order_type, currency, items, frequency, interval_days, next_run_date, anchor_date, occurrences, end_date, cancelled) \
+         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $9, 0, $10, false) \
+         RETURNING *",
+    )
+    .bind(template.created_by_user_id)
+    .bind(template.warehouse_id)
+    .bind(template.person_related_id)
+    .bind(template.order_type)
+    .bind(template.currency)
+    .bind(sqlx::types::Json(&template.items))
+    .bind(template.frequency)
+    .bind(template.interval_days)
+    .bind(template.next_run_date)
+    .bind(template.end_date)
+    .fetch_one(&pool)
+    .await
+    .map_err(internal_error)?;
+    Ok(Json(row))
+}
+
+/// Scans templates whose `next_run_date` is due, materializes each into a concrete order, and
+/// persists the advanced `next_run_date`/`occurrences` back onto the template.
+pub async fn materialize_due_scheduled_orders(
+    pool: &PgPool,
+    now: i64,
+) -> Result<Vec<Order>, sqlx::Error> {
+    let mut templates = sqlx::query_as::<_, ScheduledOrder>(
+        "SELECT * FROM scheduled_orders WHERE NOT cancelled AND next_run_date <= $1",
+    )
+    .bind(now)
+    .fetch_all(pool)
+    .await?;
+
+    let mut materialized = Vec::new();
+    for template in &mut templates {
+        let Some(order) = template.materialize(now, 0) else {
+            continue;
+        };
+        let order: Order = sqlx::query_as(
+            "INSERT INTO orders \
+             (created_by_user_id, updated_by_user_id, date, last_updated_date, person_in_charge_id, order_category_id, from_guest_order_id, from_scheduled_order_id, currency, items, total_amount, warehouse_id, person_related_id, description, order_type) \
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15) \
+             RETURNING *",
+        )
+        .bind(order.created_by_user_id)
+        .bind(order.updated_by_user_id)
+        .bind(order.date)
+        .bind(order.last_updated_date)
+        .bind(order.person_in_charge_id)
+        .bind(order.order_category_id)
+        .bind(order.from_guest_order_id)
+        .bind(order.from_scheduled_order_id)
+        .bind(order.currency)
+        .bind(sqlx::types::Json(&order.items))
+        .bind(order.total_amount)
+        .bind(order.warehouse_id)
+        .bind(order.person_related_id)
+        .bind(order.description)
+        .bind(order.order_type)
+        .fetch_one(pool)
+        .await?;
+
+        sqlx::query("UPDATE scheduled_orders SET next_run_date = $1, occurrences = $2 WHERE id = $3")
+            .bind(template.next_run_date)
+            .bind(template.occurrences)
+            .bind(template.id)
+            .execute(pool)
+            .await?;
+
+        materialized.push(order);
+    }
+    Ok(materialized)
+}
+
+/// Runs the due-template scan on demand, materializing and persisting any orders that are due.
+#[utoipa::path(
+    post,
+    path = "/scheduled-orders/run",
+    responses((status = 200, body = [Order])),
+    tag = "scheduled-orders"
+)]
+pub async fn run_scheduled_orders_job(State(pool): State<PgPool>) -> ApiResult<Json<Vec<Order>>> {
+    let now = Utc::now().timestamp();
+    let orders = materialize_due_scheduled_orders(&pool, now)
+        .await
+        .map_err(internal_error)?;
+    Ok(Json(orders))
+}
+
+/// Lists scheduled order templates matching the given filter.
+#[utoipa::path(
+    get,
+    path = "/scheduled-orders",
+    params(GetScheduledOrdersQuery),
+    responses((status = 200, body = [ScheduledOrder])),
+    tag = "scheduled-orders"
+)]
+pub async fn list_scheduled_orders(
+    State(pool): State<PgPool>,
+    Query(filter): Query<GetScheduledOrdersQuery>,
+) -> ApiResult<Json<Vec<ScheduledOrder>>> {
+    let (where_clause, args) = filter.get_where_condition();
+    let sql = format!("SELECT * FROM scheduled_orders {where_clause}");
+    let mut query = sqlx::query_as::<_, ScheduledOrder>(&sql);
+    for arg in &args {
+        query = bind_query_arg(query, arg);
+    }
+    let rows = query.fetch_all(&pool).await.map_err(internal_error)?;
+    Ok(Json(rows))
+}
+
+/// Cancels a scheduled order template so it stops materializing new orders.
+#[utoipa::path(
+    post,
+    path = "/scheduled-orders/{id}/cancel",
+    params(("id" = i64, Path, description = "Scheduled order id")),
+    responses((status = 200, body = ScheduledOrder)),
+    tag = "scheduled-orders"
+)]
+pub async fn cancel_scheduled_order(
+    State(pool): State<PgPool>,
+    Path(id): Path<i64>,
+) -> ApiResult<Json<ScheduledOrder>> {
+    let row = sqlx::query_as::<_, ScheduledOrder>(
+        "UPDATE scheduled_orders SET cancelled = true WHERE id = $1 RETURNING *",
+    )
+    .bind(id)
+    .fetch_one(&pool)
+    .await
+    .map_err(internal_error)?;
+    Ok(Json(row))
+}
@@ -1,4 +1,6 @@
 use ahash::HashSet;
+use chrono::{Months, TimeZone, Utc};
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use sqlx::prelude::FromRow;
 use strum::AsRefStr;
@@ -9,6 +11,28 @@ use crate::{
     myhelper::set_to_string,
 };
 
+/// Fixed-precision monetary amount backed by [`Decimal`].
+#[derive(
+    Debug,
+    Serialize,
+    Deserialize,
+    ToSchema,
+    sqlx::Type,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Clone,
+    Copy,
+    Default,
+)]
+#[sqlx(transparent)]
+pub struct Money(pub Decimal);
+
+impl Money {
+    pub const ZERO: Money = Money(Decimal::ZERO);
+}
+
 #[derive(
     Debug,
     Serialize,
@@ -98,7 +122,7 @@ impl Default for OrderCurrency {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[derive(Debug, Serialize, Deserialize, ToSchema, FromRow)]
 pub struct Order {
     /// Id will generated by the system.
     #[serde(default)]
@@ -125,16 +149,17 @@ pub struct Order {
     /// Order status will generated by the system.
     #[serde(default)]
     pub from_guest_order_id: i64,
+    /// Id of the `ScheduledOrder` template this order was materialized from, or `0` if it was
+    /// created directly.
+    #[serde(default)]
+    pub from_scheduled_order_id: i64,
     #[serde(default)]
     pub currency: OrderCurrency,
     #[serde(default)]
+    #[sqlx(json)]
     pub items: Vec<OrderItem>,
     #[serde(default)]
-    pub total_amount: f64,
-    #[serde(default)]
-    pub total_amount_settled: f64,
-    #[serde(default)]
-    pub order_payment_status: OrderPaymentStatus,
+    pub total_amount: Money,
     #[serde(default)]
     pub warehouse_id: i64,
     #[serde(default)]
@@ -148,11 +173,450 @@ pub struct Order {
 pub struct OrderItem {
     pub sku_id: i64,
     pub quantity: i64,
-    pub price: f64,
+    pub price: Money,
     #[serde(default)]
     pub exchanged: bool,
 }
 
+impl Order {
+    /// Exact sum of `price * quantity` across `items`, computed in `Decimal` so multi-item
+    /// totals never drift the way summing `f64` prices would.
+    pub fn compute_total_amount(items: &[OrderItem]) -> Money {
+        Money(
+            items
+                .iter()
+                .map(|item| item.price.0 * Decimal::from(item.quantity))
+                .sum(),
+        )
+    }
+
+    /// Derives `OrderPaymentStatus` from an exact comparison of `total_amount_settled` against
+    /// `total_amount`, so the `Settled`/`PartialSettled` boundary never flips due to float drift.
+    pub fn derive_payment_status(
+        total_amount: Money,
+        total_amount_settled: Money,
+    ) -> OrderPaymentStatus {
+        if total_amount_settled.0 <= Decimal::ZERO {
+            OrderPaymentStatus::Unsettled
+        } else if total_amount_settled.0 >= total_amount.0 {
+            OrderPaymentStatus::Settled
+        } else {
+            OrderPaymentStatus::PartialSettled
+        }
+    }
+}
+
+/// A single movement of money against an order. `total_amount_settled` and
+/// `order_payment_status` are no longer stored on `Order`; they're derived by summing an order's
+/// un-voided payments via [`OrderSettlement::from_payments`], so partial settlement is visible as
+/// a ledger instead of a single scalar.
+#[derive(Debug, Serialize, Deserialize, ToSchema, Clone, FromRow)]
+pub struct Payment {
+    /// Id will generated by the system.
+    #[serde(default)]
+    pub id: i64,
+    pub order_id: i64,
+    pub amount: Money,
+    pub currency: OrderCurrency,
+    pub date: i64,
+    pub method: String,
+    #[serde(default)]
+    pub note: String,
+    pub created_by_user_id: i64,
+    /// Voided payments are kept for audit history but excluded from settlement sums.
+    #[serde(default)]
+    pub voided: bool,
+}
+
+/// An order's settlement state, derived from its payments rather than carried as stored scalars.
+#[derive(Debug, Serialize, ToSchema, Clone, Copy, Default)]
+pub struct OrderSettlement {
+    pub total_amount_settled: Money,
+    pub order_payment_status: OrderPaymentStatus,
+}
+
+impl OrderSettlement {
+    /// Sums an order's un-voided payments and derives `order_payment_status` from the result.
+    pub fn from_payments(total_amount: Money, payments: &[Payment]) -> Self {
+        let total_amount_settled = Money(
+            payments
+                .iter()
+                .filter(|p| !p.voided)
+                .map(|p| p.amount.0)
+                .sum(),
+        );
+        let order_payment_status = Order::derive_payment_status(total_amount, total_amount_settled);
+        Self {
+            total_amount_settled,
+            order_payment_status,
+        }
+    }
+}
+
+/// An exchange rate for converting `from` into `to`, effective as of `as_of_date`. Rates are
+/// looked up by picking the latest `as_of_date` that does not exceed the order's own `date`.
+#[derive(Debug, Serialize, Deserialize, ToSchema, Clone, Copy, FromRow)]
+pub struct ExchangeRate {
+    pub from: OrderCurrency,
+    pub to: OrderCurrency,
+    pub as_of_date: i64,
+    pub rate: Money,
+}
+
+/// An order's `total_amount`/`total_amount_settled`, optionally converted into a requested base
+/// currency by [`ExchangeRateTable::convert_order`]. `converted` is `false` when no rate was
+/// available (e.g. the order's currency is [`OrderCurrency::Unknown`]), in which case the
+/// original, unconverted amounts are carried through instead of being dropped.
+#[derive(Debug, Serialize, ToSchema, Clone, Copy)]
+pub struct ConvertedOrderAmount {
+    pub order_id: i64,
+    pub currency: OrderCurrency,
+    pub total_amount: Money,
+    pub total_amount_settled: Money,
+    pub converted: bool,
+}
+
+/// In-memory table of [`ExchangeRate`]s used to normalize orders into a single base currency.
+pub struct ExchangeRateTable {
+    rates: Vec<ExchangeRate>,
+}
+
+impl ExchangeRateTable {
+    pub fn new(rates: Vec<ExchangeRate>) -> Self {
+        Self { rates }
+    }
+
+    /// The rate effective for `from -> to` as of `date`: the latest `as_of_date <= date` for
+    /// that currency pair, or `1` when `from == to`.
+    pub fn rate_at(&self, from: OrderCurrency, to: OrderCurrency, date: i64) -> Option<Decimal> {
+        if from == to {
+            return Some(Decimal::ONE);
+        }
+        self.rates
+            .iter()
+            .filter(|r| r.from == from && r.to == to && r.as_of_date <= date)
+            .max_by_key(|r| r.as_of_date)
+            .map(|r| r.rate.0)
+    }
+
+    /// Converts `amount` from `from` into `to` at the rate effective on `date`. Returns `None`
+    /// when either currency is [`OrderCurrency::Unknown`] or no rate is known.
+    pub fn convert(
+        &self,
+        amount: Money,
+        from: OrderCurrency,
+        to: OrderCurrency,
+        date: i64,
+    ) -> Option<Money> {
+        if from == OrderCurrency::Unknown || to == OrderCurrency::Unknown {
+            return None;
+        }
+        self.rate_at(from, to, date).map(|rate| Money(amount.0 * rate))
+    }
+
+    /// Converts an order's `total_amount` and its derived `settlement.total_amount_settled` into
+    /// `convert_to`, leaving both untouched and flagged when no rate is available.
+    pub fn convert_order(
+        &self,
+        order: &Order,
+        settlement: &OrderSettlement,
+        convert_to: OrderCurrency,
+    ) -> ConvertedOrderAmount {
+        let converted = self
+            .convert(order.total_amount, order.currency, convert_to, order.date)
+            .zip(self.convert(
+                settlement.total_amount_settled,
+                order.currency,
+                convert_to,
+                order.date,
+            ));
+        match converted {
+            Some((total_amount, total_amount_settled)) => ConvertedOrderAmount {
+                order_id: order.id,
+                currency: convert_to,
+                total_amount,
+                total_amount_settled,
+                converted: true,
+            },
+            None => ConvertedOrderAmount {
+                order_id: order.id,
+                currency: order.currency,
+                total_amount: order.total_amount,
+                total_amount_settled: settlement.total_amount_settled,
+                converted: false,
+            },
+        }
+    }
+}
+
+/// Materialization cadence for a [`ScheduledOrder`].
+#[derive(
+    Debug,
+    Serialize,
+    Deserialize,
+    ToSchema,
+    Hash,
+    sqlx::Type,
+    AsRefStr,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Clone,
+    Copy,
+)]
+pub enum Frequency {
+    Daily,
+    Weekly,
+    Monthly,
+    EveryNDays,
+}
+
+/// A recurring order template, materialized into concrete [`Order`]s by
+/// [`ScheduledOrder::materialize`], which links the generated order back via
+/// `Order::from_scheduled_order_id`.
+#[derive(Debug, Serialize, Deserialize, ToSchema, FromRow)]
+pub struct ScheduledOrder {
+    /// Id will generated by the system.
+    #[serde(default)]
+    pub id: i64,
+    pub created_by_user_id: i64,
+    pub warehouse_id: i64,
+    pub person_related_id: i64,
+    pub order_type: OrderType,
+    pub currency: OrderCurrency,
+    #[serde(default)]
+    #[sqlx(json)]
+    pub items: Vec<OrderItem>,
+    pub frequency: Frequency,
+    /// Only meaningful when `frequency` is `Frequency::EveryNDays`.
+    #[serde(default)]
+    pub interval_days: Option<i64>,
+    /// The first scheduled run date. `next_run_date` is always recomputed as `occurrences`
+    /// periods after this anchor, not chained off the previous `next_run_date`.
+    pub anchor_date: i64,
+    /// Number of times this template has materialized so far.
+    #[serde(default)]
+    pub occurrences: i64,
+    pub next_run_date: i64,
+    #[serde(default)]
+    pub end_date: Option<i64>,
+    #[serde(default)]
+    pub cancelled: bool,
+}
+
+impl Frequency {
+    const DAY_SECS: i64 = 86_400;
+
+    /// The `n`th run date after `anchor` for this frequency, using `interval_days` for
+    /// `EveryNDays`. Always computed from `anchor` rather than by repeatedly advancing the
+    /// previous result, so `Monthly` keeps its original day-of-month (e.g. the 31st) instead of
+    /// permanently settling onto whatever day a short month clamped it to.
+    pub fn nth_run_after(&self, anchor: i64, n: i64, interval_days: Option<i64>) -> i64 {
+        let n = n.max(0);
+        match self {
+            Frequency::Daily => anchor + Self::DAY_SECS * n,
+            Frequency::Weekly => anchor + Self::DAY_SECS * 7 * n,
+            Frequency::Monthly => Utc
+                .timestamp_opt(anchor, 0)
+                .single()
+                .and_then(|dt| dt.checked_add_months(Months::new(n as u32)))
+                .map(|dt| dt.timestamp())
+                .unwrap_or(anchor + Self::DAY_SECS * 30 * n),
+            Frequency::EveryNDays => anchor + Self::DAY_SECS * interval_days.unwrap_or(1).max(1) * n,
+        }
+    }
+}
+
+impl ScheduledOrder {
+    /// Clones this template into a concrete, freshly-dated [`Order`] and advances
+    /// `occurrences`/`next_run_date`. Returns `None` when the template is cancelled, not yet
+    /// due, or its `next_run_date` is past `end_date`.
+    pub fn materialize(&mut self, now: i64, new_order_id: i64) -> Option<Order> {
+        if self.cancelled || self.next_run_date > now {
+            return None;
+        }
+        if self.end_date.is_some_and(|end| self.next_run_date > end) {
+            return None;
+        }
+        let order = Order {
+            id: new_order_id,
+            created_by_user_id: self.created_by_user_id,
+            updated_by_user_id: self.created_by_user_id,
+            date: now,
+            last_updated_date: now,
+            person_in_charge_id: 0,
+            order_category_id: 0,
+            from_guest_order_id: 0,
+            from_scheduled_order_id: self.id,
+            currency: self.currency,
+            items: self.items.clone(),
+            total_amount: Order::compute_total_amount(&self.items),
+            warehouse_id: self.warehouse_id,
+            person_related_id: self.person_related_id,
+            description: String::new(),
+            order_type: self.order_type,
+        };
+        self.occurrences += 1;
+        self.next_run_date =
+            self.frequency
+                .nth_run_after(self.anchor_date, self.occurrences, self.interval_days);
+        Some(order)
+    }
+}
+
+#[derive(Debug, Deserialize, ToSchema, IntoParams)]
+pub struct GetScheduledOrdersQuery {
+    pub id: Option<i64>,
+    pub created_by_user_id: Option<i64>,
+    pub warehouse_id: Option<i64>,
+    pub frequency: Option<Frequency>,
+    pub cancelled: Option<bool>,
+}
+
+impl GetScheduledOrdersQuery {
+    pub fn empty() -> Self {
+        Self {
+            id: None,
+            created_by_user_id: None,
+            warehouse_id: None,
+            frequency: None,
+            cancelled: None,
+        }
+    }
+
+    pub fn get_where_condition(&self) -> (String, Vec<QueryArg>) {
+        let mut conditions = Vec::with_capacity(5);
+        let mut args: Vec<QueryArg> = Vec::new();
+
+        if let Some(v) = &self.id {
+            let idx = push_arg(&mut args, QueryArg::I64(*v));
+            conditions.push(format!("scheduled_orders.id=${idx}"));
+        }
+        if let Some(v) = &self.created_by_user_id {
+            let idx = push_arg(&mut args, QueryArg::I64(*v));
+            conditions.push(format!("scheduled_orders.created_by_user_id=${idx}"));
+        }
+        if let Some(v) = &self.warehouse_id {
+            let idx = push_arg(&mut args, QueryArg::I64(*v));
+            conditions.push(format!("scheduled_orders.warehouse_id=${idx}"));
+        }
+        if let Some(v) = &self.frequency {
+            let idx = push_arg(&mut args, QueryArg::Frequency(*v));
+            conditions.push(format!("scheduled_orders.frequency=${idx}"));
+        }
+        if let Some(v) = &self.cancelled {
+            conditions.push(format!("scheduled_orders.cancelled={v}"));
+        }
+        if !conditions.is_empty() {
+            (format!("WHERE {}", conditions.join(" AND ")), args)
+        } else {
+            (String::new(), args)
+        }
+    }
+}
+
+/// A single bound value for a placeholder produced by [`GetOrdersQuery::get_where_condition`].
+/// Callers bind these in order with [`bind_query_arg`] instead of interpolating user input into
+/// the SQL string.
+#[derive(Debug, Clone)]
+pub enum QueryArg {
+    I64(i64),
+    Str(String),
+    OrderType(OrderType),
+    OrderCurrency(OrderCurrency),
+    Frequency(Frequency),
+    Money(Money),
+}
+
+fn push_arg(args: &mut Vec<QueryArg>, arg: QueryArg) -> usize {
+    args.push(arg);
+    args.len()
+}
+
+/// Binds a single [`QueryArg`] onto a prepared `query_as` call, in the order it was pushed by
+/// [`push_arg`]. Lets callers turn the `(String, Vec<QueryArg>)` pairs returned by this module's
+/// `get_where_condition` methods into a runnable sqlx query without matching on the enum by hand.
+pub fn bind_query_arg<'q, O>(
+    query: sqlx::query::QueryAs<'q, sqlx::Postgres, O, sqlx::postgres::PgArguments>,
+    arg: &'q QueryArg,
+) -> sqlx::query::QueryAs<'q, sqlx::Postgres, O, sqlx::postgres::PgArguments> {
+    match arg {
+        QueryArg::I64(v) => query.bind(v),
+        QueryArg::Str(v) => query.bind(v),
+        QueryArg::OrderType(v) => query.bind(v),
+        QueryArg::OrderCurrency(v) => query.bind(v),
+        QueryArg::Frequency(v) => query.bind(v),
+        QueryArg::Money(v) => query.bind(v),
+    }
+}
+
+/// Accumulates a query's `SELECT ... FROM ...` header, `WHERE` fragment and bind list, and any
+/// trailing clauses (sorting, grouping, paging), and joins them into one SQL string.
+pub struct OrdersQueryBuilder {
+    header: String,
+    condition: String,
+    trailer: String,
+    args: Vec<QueryArg>,
+}
+
+impl OrdersQueryBuilder {
+    pub fn new(header: impl Into<String>, condition: String, args: Vec<QueryArg>) -> Self {
+        Self {
+            header: header.into(),
+            condition,
+            trailer: String::new(),
+            args,
+        }
+    }
+
+    fn push_trailer(mut self, clause: &str) -> Self {
+        if !clause.is_empty() {
+            if !self.trailer.is_empty() {
+                self.trailer.push(' ');
+            }
+            self.trailer.push_str(clause);
+        }
+        self
+    }
+
+    pub fn with_sorting(self, order: &str) -> Self {
+        self.push_trailer(order)
+    }
+
+    pub fn with_group_by(self, expr: &str) -> Self {
+        if expr.is_empty() {
+            self
+        } else {
+            self.push_trailer(&format!("GROUP BY {expr}"))
+        }
+    }
+
+    /// Appends `LIMIT`/`OFFSET`, binding both as ordinary [`QueryArg`]s so paging composes with
+    /// the rest of the builder instead of being bolted on by the caller.
+    pub fn with_paging(mut self, limit: Option<i64>, offset: Option<i64>) -> Self {
+        if let Some(limit) = limit {
+            let idx = push_arg(&mut self.args, QueryArg::I64(limit));
+            self = self.push_trailer(&format!("LIMIT ${idx}"));
+        }
+        if let Some(offset) = offset {
+            let idx = push_arg(&mut self.args, QueryArg::I64(offset));
+            self = self.push_trailer(&format!("OFFSET ${idx}"));
+        }
+        self
+    }
+
+    /// Produces the final SQL string and its positional bind list, ready for [`bind_query_arg`].
+    pub fn build(self) -> (String, Vec<QueryArg>) {
+        let sql = [self.header, self.condition, self.trailer]
+            .into_iter()
+            .filter(|s| !s.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ");
+        (sql, self.args)
+    }
+}
+
 #[derive(Debug, Deserialize, ToSchema, IntoParams)]
 pub struct GetOrdersQuery {
     pub id: Option<i64>,
@@ -172,6 +636,21 @@ pub struct GetOrdersQuery {
     pub last_updated_date_end: Option<i64>,
     pub sorters: Option<Vec<String>>,
     pub reverse: Option<HashSet<String>>,
+    /// Target currency for [`GetOrderStatsQuery`], which converts each order's amounts at the
+    /// latest `exchange_rates` rate on or before the order's `date` before summing them. Orders
+    /// with no matching rate, [`OrderCurrency::Unknown`] included, are summed at their original
+    /// amounts and are not flagged. [`GetOrdersQuery::get_where_condition`] ignores this field,
+    /// so the plain orders listing returns unconverted amounts.
+    pub convert_to: Option<OrderCurrency>,
+    /// Matches orders whose derived `total_amount_settled` (the un-voided sum of [`Payment`]s)
+    /// is at least this amount.
+    pub settled_amount_start: Option<Money>,
+    /// Matches orders whose derived `total_amount_settled` is at most this amount.
+    pub settled_amount_end: Option<Money>,
+    /// Matches orders with at least one un-voided payment on or after this date.
+    pub payment_date_start: Option<i64>,
+    /// Matches orders with at least one un-voided payment on or before this date.
+    pub payment_date_end: Option<i64>,
 }
 
 impl GetOrdersQuery {
@@ -194,78 +673,146 @@ impl GetOrdersQuery {
             reverse: None,
             last_updated_date_start: None,
             last_updated_date_end: None,
+            convert_to: None,
+            settled_amount_start: None,
+            settled_amount_end: None,
+            payment_date_start: None,
+            payment_date_end: None,
         }
     }
-    pub fn get_where_condition(&self) -> String {
+    /// Builds the `WHERE` fragment for this filter as a parameterized string plus its ordered
+    /// bind list. User-supplied values are bound positionally (`$1`, `$2`, ...) rather than
+    /// interpolated, and the caller binds them with [`bind_query_arg`]. The one exception is
+    /// `order_payment_status`, whose `OrderPaymentStatus` variant names are written into the SQL
+    /// as string literals.
+    pub fn get_where_condition(&self) -> (String, Vec<QueryArg>) {
         let mut conditions = Vec::with_capacity(5);
+        let mut args: Vec<QueryArg> = Vec::new();
         let reverse = self.reverse.as_ref();
 
         if let Some(v) = &self.id {
             let eq = eq_or_not(reverse, "id");
-            conditions.push(format!("orders.id{eq}{v}"));
+            let idx = push_arg(&mut args, QueryArg::I64(*v));
+            conditions.push(format!("orders.id{eq}${idx}"));
         }
         if let Some(v) = &self.created_by_user_id {
             let eq = eq_or_not(reverse, "created_by_user_id");
-            conditions.push(format!("orders.created_by_user_id{eq}{v}"));
+            let idx = push_arg(&mut args, QueryArg::I64(*v));
+            conditions.push(format!("orders.created_by_user_id{eq}${idx}"));
         }
         if let Some(v) = &self.updated_by_user_id {
             let eq = eq_or_not(reverse, "updated_by_user_id");
-            conditions.push(format!("orders.updated_by_user_id{eq}{v}"));
+            let idx = push_arg(&mut args, QueryArg::I64(*v));
+            conditions.push(format!("orders.updated_by_user_id{eq}${idx}"));
         }
         if let Some(v) = &self.fuzzy {
             let eq = like_or_not(reverse, "fuzzy");
-            conditions.push(format!("CAST(orders.id AS TEXT) {eq} '%{v}%' OR persons_related.name {eq} '%{v}%' OR persons_in_charge.name {eq} '%{v}%' OR order_status_list.name {eq} '%{v}%' OR warehouses.name {eq} '%{v}%'"));
+            let idx = push_arg(&mut args, QueryArg::Str(format!("%{v}%")));
+            conditions.push(format!("CAST(orders.id AS TEXT) {eq} ${idx} OR persons_related.name {eq} ${idx} OR persons_in_charge.name {eq} ${idx} OR order_status_list.name {eq} ${idx} OR warehouses.name {eq} ${idx}"));
         }
         if let Some(v) = &self.warehouse_ids {
             let eq = in_or_not(reverse, "warehouse_ids");
-            let v = set_to_string(&v, ",");
-            conditions.push(format!("orders.warehouse_id{eq}({v})"));
+            let idxs: Vec<String> = v
+                .iter()
+                .map(|id| format!("${}", push_arg(&mut args, QueryArg::I64(*id))))
+                .collect();
+            conditions.push(format!("orders.warehouse_id{eq}({})", idxs.join(",")));
         }
         if let Some(v) = &self.person_related_id {
             let eq = eq_or_not(reverse, "person_related_id");
-            conditions.push(format!("orders.person_related_id{eq}{v}"));
+            let idx = push_arg(&mut args, QueryArg::I64(*v));
+            conditions.push(format!("orders.person_related_id{eq}${idx}"));
         }
         if let Some(v) = &self.person_in_charge_id {
             let eq = eq_or_not(reverse, "person_in_charge_id");
-            conditions.push(format!("orders.person_in_charge_id{eq}{v}"));
+            let idx = push_arg(&mut args, QueryArg::I64(*v));
+            conditions.push(format!("orders.person_in_charge_id{eq}${idx}"));
         }
         if let Some(v) = &self.order_type {
             let eq = eq_or_not(reverse, "order_type");
-            conditions.push(format!("orders.order_type{eq}'{}'", v.as_ref()));
+            let idx = push_arg(&mut args, QueryArg::OrderType(*v));
+            conditions.push(format!("orders.order_type{eq}${idx}"));
         }
         if let Some(v) = &self.order_payment_status {
             let eq = in_or_not(reverse, "order_payment_status");
             let v = set_to_string(v, "','");
-            conditions.push(format!("orders.order_payment_status{eq}('{v}')"));
+            let settled = "(SELECT COALESCE(SUM(p.amount), 0) FROM payments p WHERE p.order_id = orders.id AND NOT p.voided)";
+            conditions.push(format!(
+                "(CASE \
+                   WHEN {settled} <= 0 THEN '{unsettled}' \
+                   WHEN {settled} >= orders.total_amount THEN '{settled_status}' \
+                   ELSE '{partial}' \
+                 END){eq}('{v}')",
+                unsettled = OrderPaymentStatus::Unsettled.as_ref(),
+                settled_status = OrderPaymentStatus::Settled.as_ref(),
+                partial = OrderPaymentStatus::PartialSettled.as_ref(),
+            ));
         }
         if let Some(v) = &self.order_category_id {
             let eq = eq_or_not(reverse, "order_category_id");
-            conditions.push(format!("orders.order_category_id{eq}{v}"));
+            let idx = push_arg(&mut args, QueryArg::I64(*v));
+            conditions.push(format!("orders.order_category_id{eq}${idx}"));
         }
         if let Some(v) = &self.currency {
             let eq = eq_or_not(reverse, "currency");
-            conditions.push(format!("orders.currency{eq}'{}'", v.as_ref()));
+            let idx = push_arg(&mut args, QueryArg::OrderCurrency(*v));
+            conditions.push(format!("orders.currency{eq}${idx}"));
         }
         if let Some(v) = &self.date_start {
-            conditions.push(format!("orders.date>={v}"));
+            let idx = push_arg(&mut args, QueryArg::I64(*v));
+            conditions.push(format!("orders.date>=${idx}"));
         }
         if let Some(v) = &self.date_end {
-            conditions.push(format!("orders.date<={v}"));
+            let idx = push_arg(&mut args, QueryArg::I64(*v));
+            conditions.push(format!("orders.date<=${idx}"));
         }
         if let Some(v) = &self.last_updated_date_start {
-            conditions.push(format!("orders.last_updated_date_start>={v}"));
+            let idx = push_arg(&mut args, QueryArg::I64(*v));
+            conditions.push(format!("orders.last_updated_date_start>=${idx}"));
         }
         if let Some(v) = &self.last_updated_date_end {
-            conditions.push(format!("orders.last_updated_date_end<={v}"));
+            let idx = push_arg(&mut args, QueryArg::I64(*v));
+            conditions.push(format!("orders.last_updated_date_end<=${idx}"));
+        }
+        if let Some(v) = &self.settled_amount_start {
+            let idx = push_arg(&mut args, QueryArg::Money(*v));
+            conditions.push(format!(
+                "(SELECT COALESCE(SUM(p.amount), 0) FROM payments p WHERE p.order_id = orders.id AND NOT p.voided) >= ${idx}"
+            ));
+        }
+        if let Some(v) = &self.settled_amount_end {
+            let idx = push_arg(&mut args, QueryArg::Money(*v));
+            conditions.push(format!(
+                "(SELECT COALESCE(SUM(p.amount), 0) FROM payments p WHERE p.order_id = orders.id AND NOT p.voided) <= ${idx}"
+            ));
+        }
+        if let Some(v) = &self.payment_date_start {
+            let idx = push_arg(&mut args, QueryArg::I64(*v));
+            conditions.push(format!(
+                "EXISTS (SELECT 1 FROM payments p WHERE p.order_id = orders.id AND NOT p.voided AND p.date >= ${idx})"
+            ));
+        }
+        if let Some(v) = &self.payment_date_end {
+            let idx = push_arg(&mut args, QueryArg::I64(*v));
+            conditions.push(format!(
+                "EXISTS (SELECT 1 FROM payments p WHERE p.order_id = orders.id AND NOT p.voided AND p.date <= ${idx})"
+            ));
         }
         if !conditions.is_empty() {
             let c = conditions.join(" AND ");
-            format!("WHERE {c}").into()
+            (format!("WHERE {c}"), args)
         } else {
-            "".into()
+            (String::new(), args)
         }
     }
 
+    /// Starts a fluent [`OrdersQueryBuilder`] seeded with this filter's `WHERE` fragment, bind
+    /// list and sort order, ready for the caller to append paging before calling `.build()`.
+    pub fn to_query_builder(&self, header: impl Into<String>) -> OrdersQueryBuilder {
+        let (where_clause, args) = self.get_where_condition();
+        OrdersQueryBuilder::new(header, where_clause, args).with_sorting(&self.get_order_condition())
+    }
+
     pub fn get_order_condition(&self) -> String {
         if self.sorters.is_none() {
             return "".into();
@@ -295,3 +842,233 @@ impl GetOrdersQuery {
         }
     }
 }
+
+/// The dimension [`GetOrderStatsQuery`] buckets its aggregates by.
+#[derive(Debug, Serialize, Deserialize, ToSchema, Hash, AsRefStr, PartialEq, Eq, Clone, Copy)]
+pub enum OrderStatsGroupBy {
+    OrderType,
+    Currency,
+    WarehouseId,
+    PersonRelatedId,
+    Day,
+    Week,
+    Month,
+}
+
+/// Grouped order totals: count, sum of `total_amount` and sum of the derived
+/// `total_amount_settled`, bucketed by `group_by`. Reuses [`GetOrdersQuery::get_where_condition`]
+/// so analytics respect the same filters as the plain orders listing, in one SQL round-trip.
+#[derive(Debug, Deserialize, ToSchema, IntoParams)]
+pub struct GetOrderStatsQuery {
+    #[serde(flatten)]
+    pub filter: GetOrdersQuery,
+    pub group_by: OrderStatsGroupBy,
+}
+
+/// One bucket of [`GetOrderStatsQuery`]'s result set.
+#[derive(Debug, Serialize, ToSchema, FromRow)]
+pub struct OrderStatsBucket {
+    /// Always `TEXT`-cast in SQL, since the underlying grouping column can be an integer id, an
+    /// enum, or a truncated timestamp depending on `group_by`.
+    pub bucket: String,
+    pub order_count: i64,
+    pub total_amount: Money,
+    pub total_amount_settled: Money,
+}
+
+impl GetOrderStatsQuery {
+    fn group_by_expr(&self) -> &'static str {
+        match self.group_by {
+            OrderStatsGroupBy::OrderType => "orders.order_type",
+            OrderStatsGroupBy::Currency => "orders.currency",
+            OrderStatsGroupBy::WarehouseId => "orders.warehouse_id",
+            OrderStatsGroupBy::PersonRelatedId => "orders.person_related_id",
+            OrderStatsGroupBy::Day => "date_trunc('day', to_timestamp(orders.date))",
+            OrderStatsGroupBy::Week => "date_trunc('week', to_timestamp(orders.date))",
+            OrderStatsGroupBy::Month => "date_trunc('month', to_timestamp(orders.date))",
+        }
+    }
+
+    /// Builds the single-round-trip aggregation query: `filter`'s `WHERE` fragment and bind list,
+    /// a `GROUP BY` on the requested dimension, and a per-order settlement subquery summed
+    /// alongside `total_amount`. When `filter.convert_to` is set, both sums are converted via a
+    /// lateral join against `exchange_rates`, matched on both the order's source currency and
+    /// the requested target currency, before being summed. An order with no matching rate is
+    /// summed at its original amounts.
+    pub fn to_query_builder(&self) -> OrdersQueryBuilder {
+        let (where_clause, mut args) = self.filter.get_where_condition();
+        let group = self.group_by_expr();
+        let settled_join = "LEFT JOIN LATERAL (SELECT COALESCE(SUM(amount), 0) AS settled FROM payments WHERE payments.order_id = orders.id AND NOT payments.voided) settlement ON true";
+        let (amount_expr, settled_expr, join) = match self.filter.convert_to {
+            Some(convert_to) => {
+                let idx = push_arg(&mut args, QueryArg::OrderCurrency(convert_to));
+                let rate_join = format!(
+                    "LEFT JOIN LATERAL (SELECT rate FROM exchange_rates WHERE exchange_rates.from = orders.currency AND exchange_rates.to = ${idx} AND exchange_rates.as_of_date <= orders.date ORDER BY exchange_rates.as_of_date DESC LIMIT 1) rate ON true"
+                );
+                (
+                    "orders.total_amount * COALESCE(rate.rate, 1)".to_string(),
+                    "settlement.settled * COALESCE(rate.rate, 1)".to_string(),
+                    format!("{settled_join} {rate_join}"),
+                )
+            }
+            None => (
+                "orders.total_amount".to_string(),
+                "settlement.settled".to_string(),
+                settled_join.to_string(),
+            ),
+        };
+        let header = format!(
+            "SELECT CAST({group} AS TEXT) AS bucket, COUNT(*) AS order_count, SUM({amount_expr}) AS total_amount, SUM({settled_expr}) AS total_amount_settled FROM orders {join}"
+        );
+        OrdersQueryBuilder::new(header, where_clause, args).with_group_by(group)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn money(v: i64) -> Money {
+        Money(Decimal::from(v))
+    }
+
+    #[test]
+    fn compute_total_amount_sums_fractional_prices_without_drift() {
+        let items = vec![
+            OrderItem {
+                sku_id: 1,
+                quantity: 1,
+                price: Money(Decimal::new(1, 1)), // 0.1
+                exchanged: false,
+            },
+            OrderItem {
+                sku_id: 2,
+                quantity: 1,
+                price: Money(Decimal::new(2, 1)), // 0.2
+                exchanged: false,
+            },
+        ];
+        assert_eq!(Order::compute_total_amount(&items), Money(Decimal::new(3, 1))); // 0.3
+    }
+
+    #[test]
+    fn derive_payment_status_boundaries() {
+        assert_eq!(
+            Order::derive_payment_status(money(100), money(0)),
+            OrderPaymentStatus::Unsettled
+        );
+        assert_eq!(
+            Order::derive_payment_status(money(100), money(50)),
+            OrderPaymentStatus::PartialSettled
+        );
+        assert_eq!(
+            Order::derive_payment_status(money(100), money(100)),
+            OrderPaymentStatus::Settled
+        );
+        assert_eq!(
+            Order::derive_payment_status(money(100), money(150)),
+            OrderPaymentStatus::Settled
+        );
+    }
+
+    #[test]
+    fn order_settlement_ignores_voided_payments() {
+        let payments = vec![
+            Payment {
+                id: 1,
+                order_id: 1,
+                amount: money(40),
+                currency: OrderCurrency::CNY,
+                date: 0,
+                method: "cash".into(),
+                note: String::new(),
+                created_by_user_id: 1,
+                voided: false,
+            },
+            Payment {
+                id: 2,
+                order_id: 1,
+                amount: money(60),
+                currency: OrderCurrency::CNY,
+                date: 0,
+                method: "cash".into(),
+                note: String::new(),
+                created_by_user_id: 1,
+                voided: true,
+            },
+        ];
+        let settlement = OrderSettlement::from_payments(money(100), &payments);
+        assert_eq!(settlement.total_amount_settled, money(40));
+        assert_eq!(settlement.order_payment_status, OrderPaymentStatus::PartialSettled);
+    }
+
+    #[test]
+    fn frequency_nth_run_after_daily_weekly_every_n_days() {
+        let anchor = 0;
+        assert_eq!(Frequency::Daily.nth_run_after(anchor, 1, None), 86_400);
+        assert_eq!(Frequency::Weekly.nth_run_after(anchor, 1, None), 86_400 * 7);
+        assert_eq!(Frequency::EveryNDays.nth_run_after(anchor, 1, Some(5)), 86_400 * 5);
+        assert_eq!(Frequency::EveryNDays.nth_run_after(anchor, 3, Some(5)), 86_400 * 15);
+    }
+
+    #[test]
+    fn frequency_nth_run_after_every_n_days_clamps_non_positive_interval() {
+        let anchor = 0;
+        assert_eq!(Frequency::EveryNDays.nth_run_after(anchor, 1, Some(0)), 86_400);
+        assert_eq!(Frequency::EveryNDays.nth_run_after(anchor, 1, Some(-7)), 86_400);
+    }
+
+    #[test]
+    fn frequency_nth_run_after_monthly_lands_on_calendar_month() {
+        let jan_31 = Utc.with_ymd_and_hms(2026, 1, 31, 0, 0, 0).unwrap().timestamp();
+        let first = Frequency::Monthly.nth_run_after(jan_31, 1, None);
+        let first_dt = Utc.timestamp_opt(first, 0).unwrap();
+        assert_eq!(first_dt.format("%Y-%m").to_string(), "2026-02");
+    }
+
+    #[test]
+    fn frequency_nth_run_after_monthly_does_not_drift_across_short_months() {
+        // Jan 31 -> Feb (clamped to 28) -> Mar should still land on the 31st, not drift onto
+        // whatever day the clamp picked, because each run is computed fresh from the anchor.
+        let jan_31 = Utc.with_ymd_and_hms(2026, 1, 31, 0, 0, 0).unwrap().timestamp();
+        let second = Frequency::Monthly.nth_run_after(jan_31, 2, None);
+        let second_dt = Utc.timestamp_opt(second, 0).unwrap();
+        assert_eq!(second_dt.format("%Y-%m-%d").to_string(), "2026-03-31");
+    }
+
+    #[test]
+    fn exchange_rate_table_picks_latest_effective_rate() {
+        let table = ExchangeRateTable::new(vec![
+            ExchangeRate {
+                from: OrderCurrency::USD,
+                to: OrderCurrency::CNY,
+                as_of_date: 100,
+                rate: money(7),
+            },
+            ExchangeRate {
+                from: OrderCurrency::USD,
+                to: OrderCurrency::CNY,
+                as_of_date: 200,
+                rate: Money(Decimal::new(72, 1)),
+            },
+        ]);
+        assert_eq!(
+            table.rate_at(OrderCurrency::USD, OrderCurrency::CNY, 250),
+            Some(Decimal::new(72, 1))
+        );
+        assert_eq!(
+            table.rate_at(OrderCurrency::USD, OrderCurrency::CNY, 150),
+            Some(Decimal::from(7))
+        );
+        assert_eq!(table.rate_at(OrderCurrency::USD, OrderCurrency::USD, 0), Some(Decimal::ONE));
+    }
+
+    #[test]
+    fn exchange_rate_table_refuses_unknown_currency() {
+        let table = ExchangeRateTable::new(vec![]);
+        assert_eq!(
+            table.convert(money(10), OrderCurrency::Unknown, OrderCurrency::CNY, 0),
+            None
+        );
+    }
+}